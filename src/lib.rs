@@ -1,9 +1,26 @@
 //! Thread-safe, lock-free, and atomic slice-pointers.
+//!
+//! Without the `std` feature this crate is `#![no_std]` and only needs `alloc` for its boxed
+//! storage. The `std` feature
+//! (on by default) additionally enables the blocking [`OncePtr::wait`] accessor, which parks
+//! the calling thread and therefore needs `std::thread`.
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(missing_docs)]
 #![deny(clippy::undocumented_unsafe_blocks)]
 
-mod once_slice;
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+mod once_pointee;
+mod once_ptr;
 mod once_slice_metadata;
 
-pub use once_slice::OnceSlicePtr;
-pub use once_slice_metadata::OnceSlicePtrMetadata;
\ No newline at end of file
+pub use once_pointee::OncePointee;
+pub use once_ptr::{AllocError, OncePtr, TrySetError};
+pub use once_slice_metadata::OnceSlicePtrMetadata;
+
+/// A synchronization primitive for `[T]` which can be written to only once.
+///
+/// This is an alias for the slice specialization of the generic [`OncePtr`].
+pub type OnceSlicePtr<T> = OncePtr<[T]>;
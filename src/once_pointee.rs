@@ -0,0 +1,236 @@
+use core::mem::forget;
+use core::ptr::{self, null_mut};
+use core::slice;
+use core::str;
+use core::sync::atomic::Ordering::{AcqRel, Acquire, Release};
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize};
+
+use alloc::boxed::Box;
+
+/// A type that can be published exactly once behind a lock-free atomic pointer.
+///
+/// [`OncePtr<P>`](crate::OncePtr) is generic over its pointee through this trait: it decides
+/// what atomic words are needed to hold a `Box<P>` (a single pointer for `Sized` types, a
+/// pointer, a length and a readiness flag for `[T]` and `str`), and how to load, store and drop
+/// through them. This
+/// is the same role `Sized`-vs-DST layout abstractions play in `once_cell`, decoupling the
+/// once-pointer itself from the concrete pointee representation.
+pub trait OncePointee {
+    /// The atomic storage needed to hold a pointer (and, for unsized pointees, the length
+    /// needed to reconstitute a fat pointer) to a boxed `Self`.
+    type Storage;
+
+    /// The storage for an unset [`OncePtr`](crate::OncePtr).
+    ///
+    /// Every impl is only ever copied into a fresh `OncePtr` by [`new`](crate::OncePtr::new) and
+    /// never read back through the const itself, so the interior mutability clippy's
+    /// `declare_interior_mutable_const` lint warns about isn't the footgun here; impls silence
+    /// it with `#[allow(clippy::declare_interior_mutable_const)]`.
+    const UNINIT: Self::Storage;
+
+    /// Loads a shared reference to the published value, if any.
+    fn load(storage: &Self::Storage) -> Option<&Self>;
+
+    /// Loads a mutable reference to the published value, if any.
+    fn load_mut(storage: &mut Self::Storage) -> Option<&mut Self>;
+
+    /// Tries to publish `value`, returning it back unchanged if the storage was already set.
+    fn store(storage: &Self::Storage, value: Box<Self>) -> Result<(), Box<Self>>;
+
+    /// Drops the published value in place, if any was ever published.
+    ///
+    /// # Safety
+    /// Must only be called from [`Drop`], i.e. while holding `&mut Self::Storage` and therefore
+    /// knowing no other references to the published value can be alive.
+    unsafe fn drop_in_place(storage: &mut Self::Storage);
+}
+
+impl<T> OncePointee for T {
+    type Storage = AtomicPtr<T>;
+
+    #[allow(clippy::declare_interior_mutable_const)]
+    const UNINIT: Self::Storage = AtomicPtr::new(null_mut());
+
+    fn load(storage: &Self::Storage) -> Option<&Self> {
+        let ptr = storage.load(Acquire);
+        if ptr.is_null() {
+            None
+        } else {
+            // SAFETY:
+            // `ptr` can only be set via `store` and therefore came from an owned `Box<T>` that
+            // is never freed before `self` is dropped.
+            Some(unsafe { &*ptr })
+        }
+    }
+
+    fn load_mut(storage: &mut Self::Storage) -> Option<&mut Self> {
+        let ptr = *storage.get_mut();
+        if ptr.is_null() {
+            None
+        } else {
+            // SAFETY:
+            // `ptr` can only be set via `store` and therefore came from an owned `Box<T>`.
+            // `&mut Self::Storage` guarantees no other reference to it is alive.
+            Some(unsafe { &mut *ptr })
+        }
+    }
+
+    fn store(storage: &Self::Storage, value: Box<Self>) -> Result<(), Box<Self>> {
+        let ptr = Box::into_raw(value);
+        if storage
+            .compare_exchange(null_mut(), ptr, AcqRel, Acquire)
+            .is_err()
+        {
+            // SAFETY: `ptr` still uniquely owns the `Box<T>` unwrapped above; nobody else saw it.
+            Err(unsafe { Box::from_raw(ptr) })
+        } else {
+            Ok(())
+        }
+    }
+
+    unsafe fn drop_in_place(storage: &mut Self::Storage) {
+        let ptr = *storage.get_mut();
+        if !ptr.is_null() {
+            // SAFETY:
+            // `ptr` can only be set via `store` and therefore came from an owned `Box<T>`.
+            // `&mut Self::Storage` guarantees there are no lingering references.
+            drop(unsafe { Box::from_raw(ptr) });
+        }
+    }
+}
+
+impl<T> OncePointee for [T] {
+    // An explicit readiness flag, set last, after `ptr` and `len`. Reading is keyed off this
+    // flag rather than `len != 0`, so a deliberately empty `Box<[]>` is reported as set: `ptr`
+    // can already be non-null (a dangling-but-valid pointer) while the slice is still being
+    // published, so neither `ptr` nor `len` alone can tell "unset" from "set to empty".
+    type Storage = (AtomicPtr<T>, AtomicUsize, AtomicBool);
+
+    #[allow(clippy::declare_interior_mutable_const)]
+    const UNINIT: Self::Storage = (
+        AtomicPtr::new(null_mut()),
+        AtomicUsize::new(0),
+        AtomicBool::new(false),
+    );
+
+    fn load(storage: &Self::Storage) -> Option<&Self> {
+        if !storage.2.load(Acquire) {
+            return None;
+        }
+        let ptr = storage.0.load(Acquire);
+        let len = storage.1.load(Acquire);
+        // SAFETY:
+        // `storage.2` is only set to `true` after `ptr`/`len` are written in `store`, so
+        // observing `true` here happens-after that write and `ptr`/`len` describe the
+        // `Box<[T]>` published there.
+        Some(unsafe { slice::from_raw_parts(ptr, len) })
+    }
+
+    fn load_mut(storage: &mut Self::Storage) -> Option<&mut Self> {
+        if !*storage.2.get_mut() {
+            return None;
+        }
+        let ptr = *storage.0.get_mut();
+        let len = *storage.1.get_mut();
+        // SAFETY:
+        // `ptr`/`len` describe the `Box<[T]>` published by `store`.
+        // `&mut Self::Storage` guarantees no other reference to it is alive.
+        Some(unsafe { slice::from_raw_parts_mut(ptr, len) })
+    }
+
+    fn store(storage: &Self::Storage, mut value: Box<Self>) -> Result<(), Box<Self>> {
+        let len = value.len();
+        let ptr = value.as_mut_ptr();
+        if storage
+            .0
+            .compare_exchange(null_mut(), ptr, AcqRel, Acquire)
+            .is_err()
+        {
+            Err(value)
+        } else {
+            storage.1.store(len, Release);
+            forget(value);
+            storage.2.store(true, Release);
+            Ok(())
+        }
+    }
+
+    unsafe fn drop_in_place(storage: &mut Self::Storage) {
+        let ptr = *storage.0.get_mut();
+        if !ptr.is_null() {
+            let len = *storage.1.get_mut();
+            // SAFETY:
+            // `ptr`/`len` describe the `Box<[T]>` that was forgotten in `store`.
+            drop(unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, len)) });
+        }
+    }
+}
+
+impl OncePointee for str {
+    // An explicit readiness flag, set last, after `ptr` and `len`, for the same reason as the
+    // `[T]` impl above: a reader racing `store` could otherwise observe the just-published
+    // non-null `ptr` before `len` is visible and reconstruct a torn `str` from a stale length.
+    type Storage = (AtomicPtr<u8>, AtomicUsize, AtomicBool);
+
+    #[allow(clippy::declare_interior_mutable_const)]
+    const UNINIT: Self::Storage = (
+        AtomicPtr::new(null_mut()),
+        AtomicUsize::new(0),
+        AtomicBool::new(false),
+    );
+
+    fn load(storage: &Self::Storage) -> Option<&Self> {
+        if !storage.2.load(Acquire) {
+            return None;
+        }
+        let ptr = storage.0.load(Acquire);
+        let len = storage.1.load(Acquire);
+        // SAFETY:
+        // `storage.2` is only set to `true` after `ptr`/`len` are written in `store`, so
+        // observing `true` here happens-after that write, and `ptr`/`len` describe the
+        // `Box<str>` published there, which was valid utf-8 when it was boxed and has not been
+        // mutated since.
+        Some(unsafe { str::from_utf8_unchecked(slice::from_raw_parts(ptr, len)) })
+    }
+
+    fn load_mut(storage: &mut Self::Storage) -> Option<&mut Self> {
+        if !*storage.2.get_mut() {
+            return None;
+        }
+        let ptr = *storage.0.get_mut();
+        let len = *storage.1.get_mut();
+        // SAFETY:
+        // `ptr`/`len` describe the `Box<str>` published by `store`.
+        // `&mut Self::Storage` guarantees no other reference to it is alive.
+        Some(unsafe { str::from_utf8_unchecked_mut(slice::from_raw_parts_mut(ptr, len)) })
+    }
+
+    fn store(storage: &Self::Storage, value: Box<Self>) -> Result<(), Box<Self>> {
+        let len = value.len();
+        let ptr = value.as_ptr() as *mut u8;
+        if storage
+            .0
+            .compare_exchange(null_mut(), ptr, AcqRel, Acquire)
+            .is_err()
+        {
+            Err(value)
+        } else {
+            storage.1.store(len, Release);
+            forget(value);
+            storage.2.store(true, Release);
+            Ok(())
+        }
+    }
+
+    unsafe fn drop_in_place(storage: &mut Self::Storage) {
+        let ptr = *storage.0.get_mut();
+        if !ptr.is_null() {
+            let len = *storage.1.get_mut();
+            let bytes = ptr::slice_from_raw_parts_mut(ptr, len);
+            // SAFETY:
+            // `bytes` describes the `Box<str>` that was forgotten in `store`, so casting it
+            // back to `*mut str` just restores the metadata a `Box<str>` always carries.
+            drop(unsafe { Box::from_raw(bytes as *mut str) });
+        }
+    }
+}
@@ -0,0 +1,318 @@
+use core::convert::Infallible;
+use core::fmt;
+use core::hint::spin_loop;
+use core::mem::forget;
+use core::ptr::{null_mut, NonNull};
+use core::slice;
+use core::sync::atomic::AtomicPtr;
+use core::sync::atomic::Ordering::{AcqRel, Acquire, Release};
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+#[cfg(feature = "std")]
+use std::thread::{self, Thread};
+
+use crate::OncePointee;
+
+/// A synchronization primitive for any [`OncePointee`] which can be written to only once.
+///
+/// This is heavily inspired by [`OnceLock`] and tries to follow a mostly similar API, but is
+/// generic over the pointee: `OncePtr<T>` for a boxed `Sized` value, `OncePtr<[T]>` for a slice
+/// and `OncePtr<str>` for string data all share the same implementation, dispatching to
+/// [`OncePointee`] for the pointer/length bookkeeping each representation needs.
+///
+/// It can be used in statics.
+///
+/// `OncePtr<P>` is `Send` when `P` is `Send`, and `Sync` when `P` is both `Sync` and `Send`, same
+/// as [`OnceLock`]: a non-`Send` pointee
+/// such as `Rc<i32>` makes the whole cell non-`Send`, so it can't be smuggled across threads and
+/// raced against its own non-atomic refcount.
+///
+/// ```compile_fail
+/// use atomic_slice_pointer::OncePtr;
+/// use std::rc::Rc;
+///
+/// fn assert_send<T: Send>() {}
+/// assert_send::<OncePtr<Rc<i32>>>();
+/// ```
+///
+/// [`OnceLock`]: std::sync::OnceLock
+pub struct OncePtr<P: ?Sized + OncePointee> {
+    storage: P::Storage,
+    #[cfg(feature = "std")]
+    waiters: Mutex<Vec<Thread>>,
+}
+
+// SAFETY:
+// `P::Storage` is built entirely out of atomics, and `OncePtr<P>` owns the published `Box<P>`:
+// sending the cell moves that ownership, so the receiving thread may take `&mut P` through
+// `get_mut` or drop the `P`, which is sound when `P: Send`. This mirrors
+// `std::sync::OnceLock<T>`'s `Send` impl.
+unsafe impl<P: ?Sized + OncePointee + Send> Send for OncePtr<P> {}
+// SAFETY:
+// Sharing `&OncePtr<P>` across threads lets them observe the same `&P`, which is sound exactly
+// when `P` is `Sync`; `P: Send` is also required because any sharing thread can `set` a `Box<P>`
+// it built, which is then owned, and eventually dropped, by whichever thread owns the cell (as
+// `OnceLock<T>` also requires).
+unsafe impl<P: ?Sized + OncePointee + Sync + Send> Sync for OncePtr<P> {}
+
+impl<P: ?Sized + OncePointee> Default for OncePtr<P> {
+    /// Returns an unset [`OncePtr<P>`].
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: ?Sized + OncePointee> OncePtr<P> {
+    /// Returns an unset [`OncePtr<P>`].
+    pub const fn new() -> Self {
+        Self {
+            storage: P::UNINIT,
+            #[cfg(feature = "std")]
+            waiters: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Tries to set the value from a [`Box<P>`].
+    ///
+    /// Returns:
+    /// `Ok(())` if it succeeded.
+    /// `Err(Box<P>)` if it failed, returning the given Box.
+    pub fn set(&self, value: Box<P>) -> Result<(), Box<P>> {
+        let result = P::store(&self.storage, value);
+        if result.is_ok() {
+            self.wake_waiters();
+        }
+        result
+    }
+
+    /// Gets the reference to the underlying value.
+    ///
+    /// Returns `None` if the cell is empty, or being initialized. This
+    /// method never blocks.
+    pub fn get(&self) -> Option<&P> {
+        P::load(&self.storage)
+    }
+
+    /// Gets the mutable reference to the underlying value.
+    ///
+    /// Returns `None` if the cell is empty. This method never blocks.
+    pub fn get_mut(&mut self) -> Option<&mut P> {
+        P::load_mut(&mut self.storage)
+    }
+
+    /// Blocks the current thread until the cell is set by another thread, then returns a
+    /// reference to its contents.
+    ///
+    /// If the cell is already set, this returns immediately.
+    #[cfg(feature = "std")]
+    pub fn wait(&self) -> &P {
+        loop {
+            if let Some(value) = self.get() {
+                return value;
+            }
+            {
+                let mut waiters = self.waiters.lock().unwrap();
+                // Re-check under the lock: the setter may have published and drained the
+                // waiter list between our lock-free `get()` above and taking the lock.
+                if let Some(value) = self.get() {
+                    return value;
+                }
+                waiters.push(thread::current());
+            }
+            thread::park();
+        }
+    }
+
+    /// Wakes every thread parked in [`wait`](Self::wait), called after a successful publish.
+    #[cfg(feature = "std")]
+    fn wake_waiters(&self) {
+        let mut waiters = self.waiters.lock().unwrap();
+        for waiter in waiters.drain(..) {
+            waiter.unpark();
+        }
+    }
+
+    /// No-op without the `std` feature: there are no parked waiters to wake without threads.
+    #[cfg(not(feature = "std"))]
+    fn wake_waiters(&self) {}
+}
+
+impl<T> OncePtr<[T]> {
+    /// Gets the contents, initializing it with `f` if the cell is empty.
+    ///
+    /// Many threads may call `get_or_init` concurrently with different initializing functions,
+    /// but it is guaranteed that only one function will be executed, and every caller will
+    /// observe its result.
+    ///
+    /// # Panics
+    /// If `f` panics, the cell remains uninitialized and the panic is propagated to the caller,
+    /// so a later call can retry the initialization.
+    pub fn get_or_init(&self, f: impl FnOnce() -> Box<[T]>) -> &[T] {
+        match self.get_or_try_init(|| Ok::<_, Infallible>(f())) {
+            Ok(value) => value,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Gets the contents, initializing it with `f` if the cell is empty.
+    ///
+    /// If the cell was empty and `f` failed, the error is returned and the cell is left
+    /// uninitialized, so a later call can retry the initialization.
+    ///
+    /// # Panics
+    /// If `f` panics, the cell remains uninitialized and the panic is propagated to the caller.
+    pub fn get_or_try_init<E>(&self, f: impl FnOnce() -> Result<Box<[T]>, E>) -> Result<&[T], E> {
+        let ptr_atomic: &AtomicPtr<T> = &self.storage.0;
+        let len_atomic = &self.storage.1;
+        let ready_flag = &self.storage.2;
+        let sentinel = NonNull::<T>::dangling().as_ptr();
+
+        loop {
+            // The readiness flag, not `ptr`, is authoritative for "is a value published": a
+            // deliberately empty `Box<[]>` can have the same dangling `ptr` we use as the
+            // sentinel below, so `ptr` alone can't distinguish "initializing" from "done".
+            if ready_flag.load(Acquire) {
+                let ptr = ptr_atomic.load(Acquire);
+                let len = len_atomic.load(Acquire);
+                // SAFETY:
+                // `ready_flag` is only set to `true` after `ptr`/`len` are published below, so
+                // observing `true` here happens-after that write.
+                return Ok(unsafe { slice::from_raw_parts(ptr, len) });
+            }
+
+            let ptr = ptr_atomic.load(Acquire);
+            if ptr.is_null()
+                && ptr_atomic
+                    .compare_exchange(null_mut(), sentinel, AcqRel, Acquire)
+                    .is_ok()
+            {
+                // We won the race to initialize. If `f` unwinds, this guard resets the
+                // sentinel back to null so a later caller can retry; on a normal return
+                // (success or failure) it is defused below.
+                let guard = ResetOnUnwind { ptr: ptr_atomic };
+                let result = f();
+                forget(guard);
+
+                return match result {
+                    Ok(mut boxed) => {
+                        let len = boxed.len();
+                        let real_ptr = boxed.as_mut_ptr();
+                        len_atomic.store(len, Release);
+                        ptr_atomic.store(real_ptr, Release);
+                        forget(boxed);
+                        ready_flag.store(true, Release);
+                        self.wake_waiters();
+                        // SAFETY:
+                        // `real_ptr`/`len` describe the `Box<[T]>` just forgotten above.
+                        Ok(unsafe { slice::from_raw_parts(real_ptr, len) })
+                    }
+                    Err(err) => {
+                        ptr_atomic.store(null_mut(), Release);
+                        Err(err)
+                    }
+                };
+            }
+            // Either we lost the race for the null->sentinel CAS, or another thread is still
+            // running its initializing function; spin until the readiness flag is set.
+            // `core::hint::spin_loop` keeps this usable without `std`'s thread yielding.
+            spin_loop();
+        }
+    }
+
+    /// Tries to set the slice to the `len` elements `f(0), f(1), ..., f(len - 1)`, allocating
+    /// the backing storage fallibly instead of aborting on allocation failure.
+    ///
+    /// Returns `Err(TrySetError::Alloc(_))` without calling `f` if the backing allocation
+    /// fails. Returns `Err(TrySetError::AlreadySet(_))`, carrying the freshly built slice, if
+    /// the cell was already set.
+    pub fn set_from_fn(
+        &self,
+        len: usize,
+        mut f: impl FnMut(usize) -> T,
+    ) -> Result<(), TrySetError<T>> {
+        let mut values = Vec::new();
+        values
+            .try_reserve_exact(len)
+            .map_err(|_| TrySetError::Alloc(AllocError))?;
+        for i in 0..len {
+            values.push(f(i));
+        }
+        self.set(values.into_boxed_slice())
+            .map_err(TrySetError::AlreadySet)
+    }
+
+    /// Tries to set the slice from `iter`, allocating the backing storage fallibly instead of
+    /// aborting on allocation failure.
+    ///
+    /// Returns `Err(TrySetError::Alloc(_))`, dropping whatever elements were already pulled
+    /// from `iter`, if the backing allocation fails partway through. Returns
+    /// `Err(TrySetError::AlreadySet(_))`, carrying the freshly built slice, if the cell was
+    /// already set.
+    pub fn try_set_from_iter(
+        &self,
+        iter: impl IntoIterator<Item = T>,
+    ) -> Result<(), TrySetError<T>> {
+        let mut values = Vec::new();
+        for item in iter {
+            values
+                .try_reserve(1)
+                .map_err(|_| TrySetError::Alloc(AllocError))?;
+            values.push(item);
+        }
+        self.set(values.into_boxed_slice())
+            .map_err(TrySetError::AlreadySet)
+    }
+}
+
+/// A memory allocation failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocError;
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("memory allocation failed")
+    }
+}
+
+/// The failure modes of [`OncePtr::set_from_fn`] and [`OncePtr::try_set_from_iter`].
+#[derive(Debug)]
+pub enum TrySetError<T> {
+    /// The backing allocation failed; the cell is untouched.
+    Alloc(AllocError),
+    /// The cell was already set; the freshly built value is returned unused.
+    AlreadySet(Box<[T]>),
+}
+
+impl<T> fmt::Display for TrySetError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrySetError::Alloc(err) => fmt::Display::fmt(err, f),
+            TrySetError::AlreadySet(_) => f.write_str("the cell was already set"),
+        }
+    }
+}
+
+/// Resets `ptr` back to null on drop, unless defused with [`forget`].
+///
+/// Used so that a panic inside the initializing closure of [`OncePtr::get_or_try_init`] leaves
+/// the cell retryable instead of stuck on the sentinel value forever.
+struct ResetOnUnwind<'a, T> {
+    ptr: &'a AtomicPtr<T>,
+}
+
+impl<T> Drop for ResetOnUnwind<'_, T> {
+    fn drop(&mut self) {
+        self.ptr.store(null_mut(), Release);
+    }
+}
+
+impl<P: ?Sized + OncePointee> Drop for OncePtr<P> {
+    fn drop(&mut self) {
+        // SAFETY:
+        // `&mut self` gives exclusive access to `self.storage` and `drop` runs at most once.
+        unsafe { P::drop_in_place(&mut self.storage) };
+    }
+}
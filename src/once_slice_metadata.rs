@@ -1,9 +1,11 @@
-use std::cell::UnsafeCell;
-use std::mem::{forget, MaybeUninit};
-use std::ptr::null_mut;
-use std::sync::atomic::Ordering::{AcqRel, Acquire, Release};
-use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize};
-use std::{ptr, slice};
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::AtomicBool;
+use core::sync::atomic::Ordering::{Acquire, Release};
+
+use alloc::boxed::Box;
+
+use crate::OncePtr;
 
 /// A synchronization primitive for `[T]` which can be written to only once.
 ///
@@ -17,8 +19,7 @@ use std::{ptr, slice};
 pub struct OnceSlicePtrMetadata<T, M> {
     metadata_flag: AtomicBool,
     metadata: MaybeUninit<UnsafeCell<M>>,
-    ptr: AtomicPtr<T>,
-    len: AtomicUsize,
+    slot: OncePtr<[T]>,
 }
 
 impl<T, M> Default for OnceSlicePtrMetadata<T, M> {
@@ -32,8 +33,7 @@ impl<T, M> OnceSlicePtrMetadata<T, M> {
     /// Returns an unset slice-pointer.
     pub const fn new() -> Self {
         Self {
-            ptr: AtomicPtr::new(null_mut()),
-            len: AtomicUsize::new(0),
+            slot: OncePtr::new(),
             metadata: MaybeUninit::uninit(),
             metadata_flag: AtomicBool::new(false),
         }
@@ -45,22 +45,16 @@ impl<T, M> OnceSlicePtrMetadata<T, M> {
     /// `Ok(())` if it succeeded.
     /// `Err(Box<[T]>, M)` if it failed, returning the given Box.
     pub fn set(&self, value: (Box<[T]>, M)) -> Result<(), (Box<[T]>, M)> {
-        let (mut boxed, metadata) = value;
-        let len = boxed.len();
-        let ptr = boxed.as_mut_ptr();
-        if self
-            .ptr
-            .compare_exchange(null_mut(), ptr, AcqRel, Acquire)
-            .is_err()
-        {
-            Err((boxed, metadata))
-        } else {
-            self.len.store(len, Release);
-            // SAFETY:
-            // compare exchange succeeded, therefore it is safe to write as nobody else can succeed
-            unsafe { self.metadata.assume_init_ref().get().write(metadata) };
-            forget(boxed);
-            Ok(())
+        let (boxed, metadata) = value;
+        match self.slot.set(boxed) {
+            Ok(()) => {
+                // SAFETY:
+                // `self.slot.set` succeeded, therefore it is safe to write as nobody else can succeed
+                unsafe { self.metadata.assume_init_ref().get().write(metadata) };
+                self.metadata_flag.store(true, Release);
+                Ok(())
+            }
+            Err(boxed) => Err((boxed, metadata)),
         }
     }
 
@@ -69,21 +63,9 @@ impl<T, M> OnceSlicePtrMetadata<T, M> {
     /// Returns `None` if the cell is empty, or being initialized. This
     /// method never blocks.
     pub fn get(&self) -> Option<&[T]> {
-        let ptr = self.ptr.load(Acquire);
-        if !ptr.is_null() {
-            let len = self.len.load(Acquire);
-            if len != 0 {
-                // SAFETY:
-                // `self.ptr` can only be set via [`try_set`] and therefore came from an owned Box.
-                // `self.len` can only be written with the len of the same Box from a [`try_set`]
-                Some(unsafe { slice::from_raw_parts(ptr, len) })
-            } else {
-                None
-            }
-        } else {
-            None
-        }
+        self.slot.get()
     }
+
     /// Gets the reference to the metadata.
     ///
     /// Returns `None` if the cell is empty, or being initialized. This method never blocks.
@@ -105,20 +87,7 @@ impl<T, M> OnceSlicePtrMetadata<T, M> {
     ///
     /// Returns `None` if the cell is empty. This method never blocks.
     pub fn get_mut(&mut self) -> Option<&mut [T]> {
-        let ptr = self.ptr.load(Acquire);
-        if !ptr.is_null() {
-            let len = self.len.load(Acquire);
-            if len != 0 {
-                // SAFETY:
-                // `self.ptr` can only be set via [`try_set`] and therefore came from an owned Box.
-                // `self.len` can only be written with the len of the same Box from a [`try_set`]
-                Some(unsafe { slice::from_raw_parts_mut(ptr, len) })
-            } else {
-                None
-            }
-        } else {
-            None
-        }
+        self.slot.get_mut()
     }
 
     /// Gets the mutable reference to the underlying value.
@@ -137,18 +106,3 @@ impl<T, M> OnceSlicePtrMetadata<T, M> {
         }
     }
 }
-
-impl<T, M> Drop for OnceSlicePtrMetadata<T, M> {
-    fn drop(&mut self) {
-        let ptr = self.ptr.load(Acquire);
-        if !ptr.is_null() {
-            // SAFETY:
-            // `self.ptr` can only be set via [`try_set`] and therefore came from an owned Box.
-            // `self.len` must be set, because `self.ptr` was non-null and there are no lingering
-            // references because [`drop`] takes a &mut Self, therefore `self.len` has been written
-            // in the same [`try_set`] as `self.ptr`.
-
-            unsafe { ptr::slice_from_raw_parts_mut(ptr, self.len.load(Acquire)).drop_in_place() };
-        }
-    }
-}
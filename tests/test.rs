@@ -1,8 +1,10 @@
 mod tests {
-    use atomic_slice_pointer::OnceSlicePtr;
+    use atomic_slice_pointer::{OncePtr, OnceSlicePtr, OnceSlicePtrMetadata, TrySetError};
+    #[cfg(feature = "std")]
     use std::thread::scope;
 
     #[test]
+    #[cfg(feature = "std")]
     fn test_initialization() {
         let pointer = OnceSlicePtr::<u8>::new();
         let r_pointer = &pointer;
@@ -22,4 +24,129 @@ mod tests {
         let successful_read = scope(|s| s.spawn(|| r_pointer.get().is_some()).join().unwrap());
         assert!(successful_read);
     }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_get_or_init_runs_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let pointer = OnceSlicePtr::<u8>::new();
+        let r_pointer = &pointer;
+        let calls = AtomicUsize::new(0);
+        let r_calls = &calls;
+
+        scope(|s| {
+            for _ in 0..10 {
+                s.spawn(move || {
+                    let slice = r_pointer.get_or_init(|| {
+                        r_calls.fetch_add(1, Ordering::SeqCst);
+                        vec![1; 4].into_boxed_slice()
+                    });
+                    assert_eq!(slice, &[1, 1, 1, 1]);
+                });
+            }
+        });
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_get_or_try_init_retries_after_err() {
+        let pointer = OnceSlicePtr::<u8>::new();
+
+        let err = pointer.get_or_try_init(|| Err::<Box<[u8]>, _>("boom"));
+        assert_eq!(err, Err("boom"));
+        assert!(pointer.get().is_none());
+
+        let value = pointer
+            .get_or_try_init(|| Ok::<_, &str>(vec![7].into_boxed_slice()))
+            .unwrap();
+        assert_eq!(value, &[7]);
+    }
+
+    #[test]
+    fn test_empty_slice_is_readable_once_set() {
+        let pointer = OnceSlicePtr::<u8>::new();
+        pointer.set(Vec::new().into_boxed_slice()).unwrap();
+        assert_eq!(pointer.get(), Some(&[][..]));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_wait_blocks_until_set() {
+        let pointer = OnceSlicePtr::<u8>::new();
+        let r_pointer = &pointer;
+
+        scope(|s| {
+            for _ in 0..10 {
+                s.spawn(move || {
+                    assert_eq!(r_pointer.wait(), &[9, 9, 9]);
+                });
+            }
+            s.spawn(move || {
+                r_pointer.set(vec![9; 3].into_boxed_slice()).unwrap();
+            });
+        });
+    }
+
+    #[test]
+    fn test_set_from_fn() {
+        let pointer = OnceSlicePtr::<u8>::new();
+        pointer.set_from_fn(4, |i| i as u8 * 2).unwrap();
+        assert_eq!(pointer.get(), Some(&[0, 2, 4, 6][..]));
+    }
+
+    #[test]
+    fn test_try_set_from_iter() {
+        let pointer = OnceSlicePtr::<u8>::new();
+        pointer.try_set_from_iter([1, 2, 3]).unwrap();
+        assert_eq!(pointer.get(), Some(&[1, 2, 3][..]));
+    }
+
+    #[test]
+    fn test_sized_pointee_set_and_get() {
+        let pointer = OncePtr::<u32>::new();
+        assert!(pointer.get().is_none());
+
+        pointer.set(Box::new(42)).unwrap();
+        assert_eq!(pointer.get(), Some(&42));
+
+        let rejected = pointer.set(Box::new(7)).unwrap_err();
+        assert_eq!(*rejected, 7);
+    }
+
+    #[test]
+    fn test_str_pointee_set_and_get() {
+        let pointer = OncePtr::<str>::new();
+        assert!(pointer.get().is_none());
+
+        pointer.set("hello".into()).unwrap();
+        assert_eq!(pointer.get(), Some("hello"));
+    }
+
+    #[test]
+    fn test_empty_str_is_readable_once_set() {
+        let pointer = OncePtr::<str>::new();
+        pointer.set("".into()).unwrap();
+        assert_eq!(pointer.get(), Some(""));
+    }
+
+    #[test]
+    fn test_metadata_readable_once_set() {
+        let pointer = OnceSlicePtrMetadata::<u8, u32>::new();
+        pointer.set((vec![1, 2, 3].into_boxed_slice(), 42)).unwrap();
+        assert_eq!(pointer.get(), Some(&[1, 2, 3][..]));
+        assert_eq!(pointer.get_metadata(), Some(&42));
+    }
+
+    #[test]
+    fn test_set_from_fn_already_set_returns_value() {
+        let pointer = OnceSlicePtr::<u8>::new();
+        pointer.set(vec![1].into_boxed_slice()).unwrap();
+
+        match pointer.set_from_fn(1, |_| 2) {
+            Err(TrySetError::AlreadySet(value)) => assert_eq!(&*value, &[2]),
+            other => panic!("expected AlreadySet, got {other:?}"),
+        }
+    }
 }